@@ -1,14 +1,24 @@
-//! mpv-embed: thin libmpv wrapper (Windows-first, HWND target)
+//! mpv-embed: thin libmpv wrapper (Windows, Linux, macOS)
 //! This uses libmpv core API (not render API) to set wid and control playback.
-//! It expects libmpv-2.dll to be available via PATH or alongside the app.
+//! `create_render_context` additionally offers a render-API mode as an
+//! alternative to `wid` embedding.
+//! It expects the libmpv shared library to be available via PATH/LD_LIBRARY_PATH
+//! or alongside the app.
 
 use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{
+  ErrorStrategy, ThreadSafeCallContext, ThreadsafeFunction, ThreadsafeFunctionCallMode,
+};
+use napi::{sys, JsBigInt, JsBuffer, JsFunction, JsNumber, JsUnknown, Ref};
 use napi_derive::napi;
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
+use std::mem;
 use std::os::raw::{c_char, c_int, c_void};
 use std::path::PathBuf;
 use std::ptr;
-use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread::JoinHandle;
 use libloading::Library;
 
 #[allow(non_camel_case_types)]
@@ -18,7 +28,8 @@ type mpv_handle = *mut c_void;
 type mpv_log_level = c_int;
 
 #[repr(C)]
-#[allow(non_camel_case_types)]
+#[allow(non_camel_case_types, dead_code)]
+#[derive(Clone, Copy, PartialEq, Eq)]
 enum mpv_format {
   MPV_FORMAT_NONE = 0,
   MPV_FORMAT_STRING = 1,
@@ -27,8 +38,130 @@ enum mpv_format {
   MPV_FORMAT_INT64 = 4,
   MPV_FORMAT_DOUBLE = 5,
   MPV_FORMAT_NODE = 7,
+  MPV_FORMAT_NODE_ARRAY = 8,
+  MPV_FORMAT_NODE_MAP = 9,
+  MPV_FORMAT_BYTE_ARRAY = 10,
+}
+
+/// Mirrors libmpv's `mpv_node`/`mpv_node_list`. The `u` union is a
+/// `#[repr(C)]` Rust union of all its possible fields, sized to the largest
+/// member, matching the C union's memory layout; only the field matching
+/// `format` is ever read.
+#[repr(C)]
+union mpv_node_u {
+  string: *mut c_char,
+  flag: c_int,
+  int64: i64,
+  double_: f64,
+  list: *mut mpv_node_list,
+  ba: *mut mpv_byte_array,
+}
+
+#[repr(C)]
+#[allow(non_camel_case_types)]
+struct mpv_node {
+  u: mpv_node_u,
+  format: mpv_format,
+}
+
+#[repr(C)]
+#[allow(non_camel_case_types)]
+struct mpv_node_list {
+  num: c_int,
+  values: *mut mpv_node,
+  keys: *mut *mut c_char, // null if this is an array, not a map
+}
+
+#[repr(C)]
+#[allow(non_camel_case_types)]
+struct mpv_byte_array {
+  data: *mut c_void,
+  size: usize,
+}
+
+#[repr(C)]
+#[allow(non_camel_case_types, dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum mpv_event_id {
+  MPV_EVENT_NONE = 0,
+  MPV_EVENT_SHUTDOWN = 1,
+  MPV_EVENT_LOG_MESSAGE = 2,
+  MPV_EVENT_START_FILE = 6,
+  MPV_EVENT_END_FILE = 7,
+  MPV_EVENT_FILE_LOADED = 8,
+  MPV_EVENT_SEEK = 20,
+  MPV_EVENT_PLAYBACK_RESTART = 21,
+  MPV_EVENT_PROPERTY_CHANGE = 22,
+  MPV_EVENT_QUEUE_OVERFLOW = 24,
+}
+
+impl mpv_event_id {
+  fn as_str(self) -> &'static str {
+    match self {
+      mpv_event_id::MPV_EVENT_NONE => "none",
+      mpv_event_id::MPV_EVENT_SHUTDOWN => "shutdown",
+      mpv_event_id::MPV_EVENT_LOG_MESSAGE => "log-message",
+      mpv_event_id::MPV_EVENT_START_FILE => "start-file",
+      mpv_event_id::MPV_EVENT_END_FILE => "end-file",
+      mpv_event_id::MPV_EVENT_FILE_LOADED => "file-loaded",
+      mpv_event_id::MPV_EVENT_SEEK => "seek",
+      mpv_event_id::MPV_EVENT_PLAYBACK_RESTART => "playback-restart",
+      mpv_event_id::MPV_EVENT_PROPERTY_CHANGE => "property-change",
+      mpv_event_id::MPV_EVENT_QUEUE_OVERFLOW => "queue-overflow",
+    }
+  }
+}
+
+/// Mirrors libmpv's `mpv_event` header layout; `data` is only valid for the
+/// event kinds we translate (property-change, log-message) and is read based
+/// on `event_id`.
+#[repr(C)]
+#[allow(non_camel_case_types)]
+struct mpv_event {
+  event_id: mpv_event_id,
+  error: c_int,
+  reply_userdata: u64,
+  data: *mut c_void,
 }
 
+#[repr(C)]
+#[allow(non_camel_case_types)]
+struct mpv_event_property {
+  name: *const c_char,
+  format: mpv_format,
+  data: *mut c_void,
+}
+
+#[repr(C)]
+#[allow(non_camel_case_types)]
+struct mpv_event_log_message {
+  prefix: *const c_char,
+  level: *const c_char,
+  text: *const c_char,
+  log_level: mpv_log_level,
+}
+
+type MpvStreamCbReadFn = unsafe extern "C" fn(cookie: *mut c_void, buf: *mut c_char, nbytes: u64) -> i64;
+type MpvStreamCbSeekFn = unsafe extern "C" fn(cookie: *mut c_void, offset: i64) -> i64;
+type MpvStreamCbSizeFn = unsafe extern "C" fn(cookie: *mut c_void) -> i64;
+type MpvStreamCbCloseFn = unsafe extern "C" fn(cookie: *mut c_void);
+
+/// Mirrors libmpv's `mpv_stream_cb_info`; we only ever fill in the read-only
+/// fields (no `cancel_fn`), which is all `mpv_stream_cb_add_ro` supports.
+#[repr(C)]
+#[allow(non_camel_case_types)]
+struct mpv_stream_cb_info {
+  cookie: *mut c_void,
+  read_fn: Option<MpvStreamCbReadFn>,
+  seek_fn: Option<MpvStreamCbSeekFn>,
+  size_fn: Option<MpvStreamCbSizeFn>,
+  close_fn: Option<MpvStreamCbCloseFn>,
+  cancel_fn: Option<unsafe extern "C" fn(cookie: *mut c_void)>,
+}
+
+type MpvStreamCbOpenRoFn =
+  unsafe extern "C" fn(user_data: *mut c_void, uri: *mut c_char, info: *mut mpv_stream_cb_info) -> c_int;
+
 // Function pointer types
 type MpvCreate = unsafe extern "C" fn() -> mpv_handle;
 type MpvDestroy = unsafe extern "C" fn(handle: mpv_handle);
@@ -39,6 +172,82 @@ type MpvSetProperty = unsafe extern "C" fn(handle: mpv_handle, name: *const c_ch
 type MpvGetProperty = unsafe extern "C" fn(handle: mpv_handle, name: *const c_char, format: mpv_format, data: *mut c_void) -> c_int;
 type MpvCommand = unsafe extern "C" fn(handle: mpv_handle, args: *const *const c_char) -> c_int;
 type MpvSetLogLevel = unsafe extern "C" fn(handle: mpv_handle, level: mpv_log_level) -> c_int;
+type MpvWaitEvent = unsafe extern "C" fn(handle: mpv_handle, timeout: f64) -> *mut mpv_event;
+type MpvObserveProperty = unsafe extern "C" fn(
+  handle: mpv_handle,
+  reply_userdata: u64,
+  name: *const c_char,
+  format: mpv_format,
+) -> c_int;
+type MpvRequestLogMessages = unsafe extern "C" fn(handle: mpv_handle, min_level: *const c_char) -> c_int;
+type MpvStreamCbAddRo = unsafe extern "C" fn(
+  handle: mpv_handle,
+  protocol: *const c_char,
+  user_data: *mut c_void,
+  open_fn: MpvStreamCbOpenRoFn,
+) -> c_int;
+type MpvSetPropertyString = unsafe extern "C" fn(handle: mpv_handle, name: *const c_char, data: *const c_char) -> c_int;
+type MpvGetPropertyString = unsafe extern "C" fn(handle: mpv_handle, name: *const c_char) -> *mut c_char;
+type MpvFree = unsafe extern "C" fn(data: *mut c_void);
+type MpvFreeNodeContents = unsafe extern "C" fn(node: *mut mpv_node);
+type MpvCommandNode = unsafe extern "C" fn(handle: mpv_handle, args: *mut mpv_node, result: *mut mpv_node) -> c_int;
+
+/// Opaque handle returned by `mpv_render_context_create`. Never dereferenced
+/// directly; only ever passed back into the `mpv_render_context_*` calls.
+#[allow(non_camel_case_types)]
+type mpv_render_context = *mut c_void;
+
+/// Mirrors libmpv's `mpv_render_param_type` (render.h). Only the subset this
+/// wrapper actually builds params for.
+#[repr(C)]
+#[allow(non_camel_case_types, dead_code)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum mpv_render_param_type {
+  MPV_RENDER_PARAM_INVALID = 0,
+  MPV_RENDER_PARAM_API_TYPE = 1,
+  MPV_RENDER_PARAM_OPENGL_INIT_PARAMS = 2,
+  MPV_RENDER_PARAM_OPENGL_FBO = 3,
+  MPV_RENDER_PARAM_FLIP_Y = 4,
+  MPV_RENDER_PARAM_SW_SIZE = 17,
+  MPV_RENDER_PARAM_SW_FORMAT = 18,
+  MPV_RENDER_PARAM_SW_STRIDE = 19,
+  MPV_RENDER_PARAM_SW_POINTER = 20,
+}
+
+#[repr(C)]
+#[allow(non_camel_case_types)]
+struct mpv_render_param {
+  type_: mpv_render_param_type,
+  data: *mut c_void,
+}
+
+type MpvGetProcAddressFn = unsafe extern "C" fn(ctx: *mut c_void, name: *const c_char) -> *mut c_void;
+
+#[repr(C)]
+#[allow(non_camel_case_types)]
+struct mpv_opengl_init_params {
+  get_proc_address: MpvGetProcAddressFn,
+  get_proc_address_ctx: *mut c_void,
+}
+
+#[repr(C)]
+#[allow(non_camel_case_types)]
+struct mpv_opengl_fbo {
+  fbo: c_int,
+  w: c_int,
+  h: c_int,
+  internal_format: c_int,
+}
+
+type MpvRenderUpdateFn = unsafe extern "C" fn(cb_ctx: *mut c_void);
+type MpvRenderContextCreate =
+  unsafe extern "C" fn(res: *mut mpv_render_context, mpv: mpv_handle, params: *mut mpv_render_param) -> c_int;
+type MpvRenderContextSetUpdateCallback =
+  unsafe extern "C" fn(ctx: mpv_render_context, callback: MpvRenderUpdateFn, callback_ctx: *mut c_void);
+type MpvRenderContextRender = unsafe extern "C" fn(ctx: mpv_render_context, params: *mut mpv_render_param) -> c_int;
+type MpvRenderContextFree = unsafe extern "C" fn(ctx: mpv_render_context);
+
+type MpvErrorString = unsafe extern "C" fn(error: c_int) -> *const c_char;
 
 struct MpvFunctions {
   create: MpvCreate,
@@ -50,6 +259,21 @@ struct MpvFunctions {
   get_property: MpvGetProperty,
   command: MpvCommand,
   set_log_level: Option<MpvSetLogLevel>, // Optional - may not be available in all DLL versions
+  wait_event: MpvWaitEvent,
+  observe_property: MpvObserveProperty,
+  request_log_messages: MpvRequestLogMessages,
+  stream_cb_add_ro: Option<MpvStreamCbAddRo>, // Optional - needs libmpv with stream_cb support
+  set_property_string: MpvSetPropertyString,
+  get_property_string: MpvGetPropertyString,
+  free: MpvFree,
+  free_node_contents: MpvFreeNodeContents,
+  command_node: MpvCommandNode,
+  // Render API - optional, needs libmpv built with render API support.
+  render_context_create: Option<MpvRenderContextCreate>,
+  render_context_set_update_callback: Option<MpvRenderContextSetUpdateCallback>,
+  render_context_render: Option<MpvRenderContextRender>,
+  render_context_free: Option<MpvRenderContextFree>,
+  error_string: Option<MpvErrorString>, // Optional - present on all known libmpv versions, but guarded like the rest
   _lib: Library, // Keep library loaded
 }
 
@@ -74,7 +298,34 @@ unsafe fn load_symbols_from_lib(lib: Library) -> std::result::Result<MpvFunction
     .map_err(|e| format!("mpv_command: {}", e))?;
   // mpv_set_log_level is optional - may not be available in all DLL versions
   let set_log_level = lib.get::<MpvSetLogLevel>(b"mpv_set_log_level\0").ok().map(|s| *s);
-  
+  let wait_event = lib.get::<MpvWaitEvent>(b"mpv_wait_event\0")
+    .map_err(|e| format!("mpv_wait_event: {}", e))?;
+  let observe_property = lib.get::<MpvObserveProperty>(b"mpv_observe_property\0")
+    .map_err(|e| format!("mpv_observe_property: {}", e))?;
+  let request_log_messages = lib.get::<MpvRequestLogMessages>(b"mpv_request_log_messages\0")
+    .map_err(|e| format!("mpv_request_log_messages: {}", e))?;
+  // mpv_stream_cb_add_ro is optional - only present on libmpv built with custom protocol support
+  let stream_cb_add_ro = lib.get::<MpvStreamCbAddRo>(b"mpv_stream_cb_add_ro\0").ok().map(|s| *s);
+  let set_property_string = lib.get::<MpvSetPropertyString>(b"mpv_set_property_string\0")
+    .map_err(|e| format!("mpv_set_property_string: {}", e))?;
+  let get_property_string = lib.get::<MpvGetPropertyString>(b"mpv_get_property_string\0")
+    .map_err(|e| format!("mpv_get_property_string: {}", e))?;
+  let free = lib.get::<MpvFree>(b"mpv_free\0")
+    .map_err(|e| format!("mpv_free: {}", e))?;
+  let free_node_contents = lib.get::<MpvFreeNodeContents>(b"mpv_free_node_contents\0")
+    .map_err(|e| format!("mpv_free_node_contents: {}", e))?;
+  let command_node = lib.get::<MpvCommandNode>(b"mpv_command_node\0")
+    .map_err(|e| format!("mpv_command_node: {}", e))?;
+  // Render API - optional, only present on libmpv built with it enabled.
+  let render_context_create = lib.get::<MpvRenderContextCreate>(b"mpv_render_context_create\0").ok().map(|s| *s);
+  let render_context_set_update_callback = lib
+    .get::<MpvRenderContextSetUpdateCallback>(b"mpv_render_context_set_update_callback\0")
+    .ok()
+    .map(|s| *s);
+  let render_context_render = lib.get::<MpvRenderContextRender>(b"mpv_render_context_render\0").ok().map(|s| *s);
+  let render_context_free = lib.get::<MpvRenderContextFree>(b"mpv_render_context_free\0").ok().map(|s| *s);
+  let error_string = lib.get::<MpvErrorString>(b"mpv_error_string\0").ok().map(|s| *s);
+
   Ok(MpvFunctions {
     create: *create,
     destroy: *destroy,
@@ -85,13 +336,32 @@ unsafe fn load_symbols_from_lib(lib: Library) -> std::result::Result<MpvFunction
     get_property: *get_property,
     command: *command,
     set_log_level,
+    wait_event: *wait_event,
+    observe_property: *observe_property,
+    request_log_messages: *request_log_messages,
+    stream_cb_add_ro,
+    set_property_string: *set_property_string,
+    get_property_string: *get_property_string,
+    free: *free,
+    free_node_contents: *free_node_contents,
+    command_node: *command_node,
+    render_context_create,
+    render_context_set_update_callback,
+    render_context_render,
+    render_context_free,
+    error_string,
     _lib: lib,
   })
 }
 
 fn load_mpv_library() -> std::result::Result<MpvFunctions, String> {
-    // Try common DLL names on Windows
+    // Try common library names for the host platform.
+    #[cfg(target_os = "windows")]
     let dll_names = ["libmpv-2.dll", "mpv.dll", "libmpv.dll"];
+    #[cfg(target_os = "macos")]
+    let dll_names = ["libmpv.2.dylib", "libmpv.dylib"];
+    #[cfg(all(unix, not(target_os = "macos")))]
+    let dll_names = ["libmpv.so.2", "libmpv.so.1", "libmpv.so"];
     let mut last_err = None;
     
     // Build search paths: current dir, exe dir, exe dir/../resources/mpv-sdk, project root mpv-sdk
@@ -174,18 +444,555 @@ fn get_mpv_funcs() -> Result<&'static MpvFunctions> {
   }
 }
 
+/// Looks up mpv's own description for an error code (e.g. "loading failed")
+/// via `mpv_error_string`, when that symbol is available.
+fn mpv_error_message(code: c_int) -> Option<String> {
+  let error_string = get_mpv_funcs().ok()?.error_string?;
+  let raw = unsafe { error_string(code) };
+  if raw.is_null() {
+    return None;
+  }
+  Some(unsafe { CStr::from_ptr(raw) }.to_string_lossy().into_owned())
+}
+
 fn check_err(code: c_int, ctx: &str) -> Result<()> {
   if code < 0 {
-    Err(Error::from_reason(format!("mpv error {}: {}", code, ctx)))
+    match mpv_error_message(code) {
+      Some(msg) => Err(Error::from_reason(format!("mpv error {code} ({msg}): {ctx}"))),
+      None => Err(Error::from_reason(format!("mpv error {code}: {ctx}"))),
+    }
   } else {
     Ok(())
   }
 }
 
+/// Owns the Rust-side allocations (`CString`s, boxed node-list arrays) that
+/// back a transient `mpv_node` tree we hand to mpv through `set_property`/
+/// `command_node`. mpv only reads from this while the call is in progress
+/// and never frees it, so it just needs to outlive that one call. The
+/// variant fields are never read back in Rust; they exist purely to keep
+/// the backing allocations alive until `OwnedNode` is dropped.
+#[allow(dead_code)]
+enum OwnedNode {
+  Leaf,
+  Str(CString),
+  Array(Vec<OwnedNode>, Vec<mpv_node>, Box<mpv_node_list>),
+  Map(Vec<OwnedNode>, Vec<mpv_node>, Vec<CString>, Vec<*mut c_char>, Box<mpv_node_list>),
+}
+
+fn json_to_mpv_node(value: &serde_json::Value) -> Result<(mpv_node, OwnedNode)> {
+  use serde_json::Value;
+  match value {
+    Value::Null => Ok((
+      mpv_node { u: mpv_node_u { int64: 0 }, format: mpv_format::MPV_FORMAT_NONE },
+      OwnedNode::Leaf,
+    )),
+    Value::Bool(b) => Ok((
+      mpv_node { u: mpv_node_u { flag: *b as c_int }, format: mpv_format::MPV_FORMAT_FLAG },
+      OwnedNode::Leaf,
+    )),
+    Value::Number(n) => {
+      if let Some(i) = n.as_i64() {
+        Ok((mpv_node { u: mpv_node_u { int64: i }, format: mpv_format::MPV_FORMAT_INT64 }, OwnedNode::Leaf))
+      } else {
+        let d = n.as_f64().ok_or_else(|| Error::from_reason("invalid number"))?;
+        Ok((mpv_node { u: mpv_node_u { double_: d }, format: mpv_format::MPV_FORMAT_DOUBLE }, OwnedNode::Leaf))
+      }
+    }
+    Value::String(s) => {
+      let cs = CString::new(s.as_str()).map_err(|e| Error::from_reason(e.to_string()))?;
+      let ptr = cs.as_ptr() as *mut c_char;
+      Ok((mpv_node { u: mpv_node_u { string: ptr }, format: mpv_format::MPV_FORMAT_STRING }, OwnedNode::Str(cs)))
+    }
+    Value::Array(items) => {
+      let mut owners = Vec::with_capacity(items.len());
+      let mut nodes = Vec::with_capacity(items.len());
+      for item in items {
+        let (node, owner) = json_to_mpv_node(item)?;
+        nodes.push(node);
+        owners.push(owner);
+      }
+      let mut list = Box::new(mpv_node_list { num: nodes.len() as c_int, values: nodes.as_mut_ptr(), keys: ptr::null_mut() });
+      let list_ptr = list.as_mut() as *mut mpv_node_list;
+      Ok((
+        mpv_node { u: mpv_node_u { list: list_ptr }, format: mpv_format::MPV_FORMAT_NODE_ARRAY },
+        OwnedNode::Array(owners, nodes, list),
+      ))
+    }
+    Value::Object(map) => {
+      let mut owners = Vec::with_capacity(map.len());
+      let mut nodes = Vec::with_capacity(map.len());
+      let mut key_strings = Vec::with_capacity(map.len());
+      for (key, val) in map {
+        let (node, owner) = json_to_mpv_node(val)?;
+        nodes.push(node);
+        owners.push(owner);
+        key_strings.push(CString::new(key.as_str()).map_err(|e| Error::from_reason(e.to_string()))?);
+      }
+      let mut key_ptrs: Vec<*mut c_char> = key_strings.iter().map(|k| k.as_ptr() as *mut c_char).collect();
+      let mut list = Box::new(mpv_node_list {
+        num: nodes.len() as c_int,
+        values: nodes.as_mut_ptr(),
+        keys: key_ptrs.as_mut_ptr(),
+      });
+      let list_ptr = list.as_mut() as *mut mpv_node_list;
+      Ok((
+        mpv_node { u: mpv_node_u { list: list_ptr }, format: mpv_format::MPV_FORMAT_NODE_MAP },
+        OwnedNode::Map(owners, nodes, key_strings, key_ptrs, list),
+      ))
+    }
+  }
+}
+
+/// Reads an `mpv_node` that mpv itself allocated (from `get_property_node`
+/// or `command_node`'s result) into a `serde_json::Value`. Caller still
+/// needs to `mpv_free_node_contents` the source node afterward.
+unsafe fn mpv_node_to_json(node: &mpv_node) -> Result<serde_json::Value> {
+  match node.format {
+    mpv_format::MPV_FORMAT_NONE => Ok(serde_json::Value::Null),
+    // mpv never tags a node with the generic NODE format itself; it's only
+    // used as the *container* format passed to get_property/command_node.
+    mpv_format::MPV_FORMAT_NODE => Err(Error::from_reason("unexpected MPV_FORMAT_NODE in node tree")),
+    mpv_format::MPV_FORMAT_FLAG => Ok(serde_json::Value::Bool(node.u.flag != 0)),
+    mpv_format::MPV_FORMAT_INT64 => Ok(serde_json::Value::from(node.u.int64)),
+    mpv_format::MPV_FORMAT_DOUBLE => Ok(
+      serde_json::Number::from_f64(node.u.double_)
+        .map(serde_json::Value::Number)
+        .unwrap_or(serde_json::Value::Null),
+    ),
+    mpv_format::MPV_FORMAT_STRING | mpv_format::MPV_FORMAT_OSD_STRING => {
+      if node.u.string.is_null() {
+        Ok(serde_json::Value::Null)
+      } else {
+        Ok(serde_json::Value::String(CStr::from_ptr(node.u.string).to_string_lossy().into_owned()))
+      }
+    }
+    mpv_format::MPV_FORMAT_NODE_ARRAY => {
+      let list = &*node.u.list;
+      let mut out = Vec::with_capacity(list.num as usize);
+      for i in 0..list.num as isize {
+        out.push(mpv_node_to_json(&*list.values.offset(i))?);
+      }
+      Ok(serde_json::Value::Array(out))
+    }
+    mpv_format::MPV_FORMAT_NODE_MAP => {
+      let list = &*node.u.list;
+      let mut out = serde_json::Map::with_capacity(list.num as usize);
+      for i in 0..list.num as isize {
+        let key = CStr::from_ptr(*list.keys.offset(i)).to_string_lossy().into_owned();
+        out.insert(key, mpv_node_to_json(&*list.values.offset(i))?);
+      }
+      Ok(serde_json::Value::Object(out))
+    }
+    mpv_format::MPV_FORMAT_BYTE_ARRAY => {
+      let ba = &*node.u.ba;
+      let bytes = std::slice::from_raw_parts(ba.data as *const u8, ba.size);
+      Ok(serde_json::Value::Array(bytes.iter().map(|b| serde_json::Value::from(*b)).collect()))
+    }
+  }
+}
+
+/// Wraps the raw handle so it can be moved into the event pump thread.
+/// Safe because mpv explicitly supports calling `mpv_wait_event` from a
+/// dedicated thread while other API calls happen on the owning thread.
+struct SendHandle(mpv_handle);
+unsafe impl Send for SendHandle {}
+
+/// Holds the JS `getProcAddress` callback for the lifetime of an opengl
+/// render context, so the `MpvGetProcAddressFn` trampoline can call back
+/// into JS. Only ever touched synchronously from `create_render_context`/
+/// `render`, which the host must call on the JS thread - same contract as
+/// `SendHandle` above, just for an `Env` instead of a raw mpv handle.
+struct ProcAddressState {
+  env: sys::napi_env,
+  get_proc_address: Ref<()>,
+}
+unsafe impl Send for ProcAddressState {}
+
+impl Drop for ProcAddressState {
+  fn drop(&mut self) {
+    // napi's Ref asserts its count is 0 when dropped (potential leak
+    // otherwise); unref it ourselves since nothing else ever does.
+    let env = unsafe { Env::from_raw(self.env) };
+    let _ = self.get_proc_address.unref(env);
+  }
+}
+
+unsafe extern "C" fn gl_get_proc_address_trampoline(ctx: *mut c_void, name: *const c_char) -> *mut c_void {
+  if ctx.is_null() || name.is_null() {
+    return ptr::null_mut();
+  }
+  let state = &*(ctx as *const ProcAddressState);
+  let env = Env::from_raw(state.env);
+  let func: JsFunction = match env.get_reference_value(&state.get_proc_address) {
+    Ok(f) => f,
+    Err(_) => return ptr::null_mut(),
+  };
+  let name_str = CStr::from_ptr(name).to_string_lossy();
+  let js_name = match env.create_string(&name_str) {
+    Ok(s) => s,
+    Err(_) => return ptr::null_mut(),
+  };
+  let result = match func.call(None, &[js_name]) {
+    Ok(r) => r,
+    Err(_) => return ptr::null_mut(),
+  };
+  let addr: Result<i64> = match result.get_type() {
+    Ok(ValueType::Number) => unsafe { result.cast::<JsNumber>() }.get_int64(),
+    Ok(ValueType::BigInt) => unsafe { result.cast::<JsBigInt>() }.get_i64().map(|(v, _)| v),
+    _ => return ptr::null_mut(),
+  };
+  match addr {
+    Ok(v) => v as usize as *mut c_void,
+    Err(_) => ptr::null_mut(),
+  }
+}
+
+/// Fires whenever mpv has a new frame ready via the render API. Runs on
+/// whatever thread mpv chooses to call it from (often its own internal
+/// video thread), so it only signals JS through the threadsafe function -
+/// the actual `render()` call still happens on the JS/GL thread.
+struct RenderUpdateState {
+  tsfn: ThreadsafeFunction<u32, ErrorStrategy::Fatal>,
+  frame: AtomicU32,
+}
+
+unsafe extern "C" fn render_update_trampoline(cb_ctx: *mut c_void) {
+  if cb_ctx.is_null() {
+    return;
+  }
+  let state = &*(cb_ctx as *const RenderUpdateState);
+  let frame = state.frame.fetch_add(1, Ordering::SeqCst).wrapping_add(1);
+  let _ = state.tsfn.call(frame, ThreadsafeFunctionCallMode::NonBlocking);
+}
+
+/// Live render-API context created by `create_render_context`. Torn down by
+/// `free_render_context`/`Drop`, always before `mpv_destroy`. `update` and
+/// `proc_address` are never read back in Rust; they exist purely to keep
+/// the TSFN/JS-ref backing allocations alive for as long as mpv can still
+/// call their trampolines.
+#[allow(dead_code)]
+struct MpvRenderContextState {
+  ctx: mpv_render_context,
+  update: Box<RenderUpdateState>,
+  // Kept alive for as long as the context exists; the opengl proc-address
+  // trampoline holds a raw pointer into it.
+  proc_address: Option<Box<ProcAddressState>>,
+}
+
+type OpenCall = (String, std::sync::mpsc::Sender<Result<i64>>);
+type ReadCall = (i64, u64, std::sync::mpsc::Sender<Result<Vec<u8>>>);
+type SeekCall = (i64, i64, std::sync::mpsc::Sender<Result<i64>>);
+type SizeCall = (i64, std::sync::mpsc::Sender<Result<i64>>);
+type CloseCall = (i64, std::sync::mpsc::Sender<Result<()>>);
+
+/// JS-backed handlers for one `register_protocol` scheme. Each field bridges
+/// a synchronous JS call (`open`/`read`/`seek`/`size`/`close`) from mpv's
+/// demuxer thread onto the JS thread and blocks for the return value: the
+/// threadsafe-function callback runs on the JS thread, calls the stored
+/// function reference directly via `JsFunction::call`, and ships the result
+/// back over an `mpsc` channel. The threadsafe function itself is created
+/// from a no-op vehicle function since napi also auto-invokes whatever
+/// function it was built from with the callback's returned args, which we
+/// don't want done twice.
+struct ProtocolHandlers {
+  open: ThreadsafeFunction<OpenCall, ErrorStrategy::Fatal>,
+  read: ThreadsafeFunction<ReadCall, ErrorStrategy::Fatal>,
+  seek: ThreadsafeFunction<SeekCall, ErrorStrategy::Fatal>,
+  size: ThreadsafeFunction<SizeCall, ErrorStrategy::Fatal>,
+  close: ThreadsafeFunction<CloseCall, ErrorStrategy::Fatal>,
+  // One `Ref` per handler, shared with that handler's tsfn closure (which
+  // resolves the callable JsFunction from it on every call). Kept here too
+  // so Drop can unref all five before napi's own Ref::drop would otherwise
+  // assert on a non-zero count.
+  env: sys::napi_env,
+  refs: Vec<Arc<Mutex<Option<Ref<()>>>>>,
+}
+
+impl Drop for ProtocolHandlers {
+  fn drop(&mut self) {
+    let env = unsafe { Env::from_raw(self.env) };
+    for r in &self.refs {
+      if let Ok(mut guard) = r.lock() {
+        if let Some(mut r) = guard.take() {
+          let _ = r.unref(env);
+        }
+      }
+    }
+  }
+}
+
+impl ProtocolHandlers {
+  fn call_open(&self, uri: String) -> Result<i64> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    self.open.call((uri, tx), ThreadsafeFunctionCallMode::NonBlocking);
+    rx.recv().map_err(|e| Error::from_reason(e.to_string()))?
+  }
+
+  fn call_read(&self, stream_id: i64, size: u64) -> Result<Vec<u8>> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    self.read.call((stream_id, size, tx), ThreadsafeFunctionCallMode::NonBlocking);
+    rx.recv().map_err(|e| Error::from_reason(e.to_string()))?
+  }
+
+  fn call_seek(&self, stream_id: i64, offset: i64) -> Result<i64> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    self.seek.call((stream_id, offset, tx), ThreadsafeFunctionCallMode::NonBlocking);
+    rx.recv().map_err(|e| Error::from_reason(e.to_string()))?
+  }
+
+  fn call_size(&self, stream_id: i64) -> Result<i64> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    self.size.call((stream_id, tx), ThreadsafeFunctionCallMode::NonBlocking);
+    rx.recv().map_err(|e| Error::from_reason(e.to_string()))?
+  }
+
+  fn call_close(&self, stream_id: i64) -> Result<()> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    self.close.call((stream_id, tx), ThreadsafeFunctionCallMode::NonBlocking);
+    rx.recv().map_err(|e| Error::from_reason(e.to_string()))?
+  }
+}
+
+/// Cookie handed to mpv for one open stream; `handlers` outlives every
+/// stream because `MpvHandle` keeps the owning `ProtocolHandlers` boxed for
+/// its own lifetime (see `MpvHandle::protocols`).
+struct StreamCookie {
+  stream_id: i64,
+  handlers: *const ProtocolHandlers,
+}
+
+unsafe extern "C" fn protocol_open_trampoline(
+  user_data: *mut c_void,
+  uri: *mut c_char,
+  info: *mut mpv_stream_cb_info,
+) -> c_int {
+  let handlers_ptr = user_data as *const ProtocolHandlers;
+  let handlers = &*handlers_ptr;
+  let uri = CStr::from_ptr(uri).to_string_lossy().into_owned();
+  match handlers.call_open(uri) {
+    Ok(stream_id) => {
+      let cookie = Box::into_raw(Box::new(StreamCookie { stream_id, handlers: handlers_ptr }));
+      let info = &mut *info;
+      info.cookie = cookie as *mut c_void;
+      info.read_fn = Some(protocol_read_trampoline);
+      info.seek_fn = Some(protocol_seek_trampoline);
+      info.size_fn = Some(protocol_size_trampoline);
+      info.close_fn = Some(protocol_close_trampoline);
+      info.cancel_fn = None;
+      0
+    }
+    Err(_) => -1, // MPV_ERROR_UNKNOWN_FORMAT-ish: generic open failure
+  }
+}
+
+unsafe extern "C" fn protocol_read_trampoline(cookie: *mut c_void, buf: *mut c_char, nbytes: u64) -> i64 {
+  let cookie = &*(cookie as *const StreamCookie);
+  let handlers = &*cookie.handlers;
+  match handlers.call_read(cookie.stream_id, nbytes) {
+    Ok(bytes) => {
+      let n = bytes.len().min(nbytes as usize);
+      if n > 0 {
+        ptr::copy_nonoverlapping(bytes.as_ptr(), buf as *mut u8, n);
+      }
+      n as i64 // 0 == EOF
+    }
+    Err(_) => -1,
+  }
+}
+
+unsafe extern "C" fn protocol_seek_trampoline(cookie: *mut c_void, offset: i64) -> i64 {
+  let cookie = &*(cookie as *const StreamCookie);
+  let handlers = &*cookie.handlers;
+  handlers.call_seek(cookie.stream_id, offset).unwrap_or(-1) // negative == unseekable
+}
+
+unsafe extern "C" fn protocol_size_trampoline(cookie: *mut c_void) -> i64 {
+  let cookie = &*(cookie as *const StreamCookie);
+  let handlers = &*cookie.handlers;
+  handlers.call_size(cookie.stream_id).unwrap_or(-1) // negative == unknown size
+}
+
+unsafe extern "C" fn protocol_close_trampoline(cookie: *mut c_void) {
+  let boxed = Box::from_raw(cookie as *mut StreamCookie);
+  let handlers = &*boxed.handlers;
+  let _ = handlers.call_close(boxed.stream_id);
+  // `boxed` drops here, freeing the cookie.
+}
+
+/// No-op N-API callback used purely as a vehicle to create threadsafe
+/// functions that call their real JS target manually (see `register_protocol`).
+unsafe extern "C" fn js_noop_callback(env: sys::napi_env, _info: sys::napi_callback_info) -> sys::napi_value {
+  let mut undefined = ptr::null_mut();
+  sys::napi_get_undefined(env, &mut undefined);
+  undefined
+}
+
+/// Owned, `Send` snapshot of an `mpv_event`, built on the event thread
+/// before the event pointer mpv gave us becomes invalid, then handed to
+/// the threadsafe function to turn into a JS object on the JS thread.
+struct EventPayload {
+  event: &'static str,
+  name: Option<String>,
+  value: Option<PropertyValue>,
+  prefix: Option<String>,
+  level: Option<String>,
+  text: Option<String>,
+}
+
+enum PropertyValue {
+  Flag(bool),
+  Int(i64),
+  Double(f64),
+  Str(String),
+}
+
+impl EventPayload {
+  fn from_raw(event: &mpv_event) -> Self {
+    let mut payload = EventPayload {
+      event: event.event_id.as_str(),
+      name: None,
+      value: None,
+      prefix: None,
+      level: None,
+      text: None,
+    };
+    if event.data.is_null() {
+      return payload;
+    }
+    match event.event_id {
+      mpv_event_id::MPV_EVENT_PROPERTY_CHANGE => {
+        let prop = unsafe { &*(event.data as *const mpv_event_property) };
+        if !prop.name.is_null() {
+          payload.name = Some(unsafe { CStr::from_ptr(prop.name) }.to_string_lossy().into_owned());
+        }
+        if !prop.data.is_null() {
+          payload.value = match prop.format {
+            mpv_format::MPV_FORMAT_FLAG => Some(PropertyValue::Flag(unsafe { *(prop.data as *const c_int) } != 0)),
+            mpv_format::MPV_FORMAT_INT64 => Some(PropertyValue::Int(unsafe { *(prop.data as *const i64) })),
+            mpv_format::MPV_FORMAT_DOUBLE => Some(PropertyValue::Double(unsafe { *(prop.data as *const f64) })),
+            mpv_format::MPV_FORMAT_STRING => {
+              let ptr = unsafe { *(prop.data as *const *const c_char) };
+              if ptr.is_null() {
+                None
+              } else {
+                Some(PropertyValue::Str(unsafe { CStr::from_ptr(ptr) }.to_string_lossy().into_owned()))
+              }
+            }
+            _ => None,
+          };
+        }
+      }
+      mpv_event_id::MPV_EVENT_LOG_MESSAGE => {
+        let msg = unsafe { &*(event.data as *const mpv_event_log_message) };
+        if !msg.prefix.is_null() {
+          payload.prefix = Some(unsafe { CStr::from_ptr(msg.prefix) }.to_string_lossy().into_owned());
+        }
+        if !msg.level.is_null() {
+          payload.level = Some(unsafe { CStr::from_ptr(msg.level) }.to_string_lossy().into_owned());
+        }
+        if !msg.text.is_null() {
+          payload.text = Some(unsafe { CStr::from_ptr(msg.text) }.to_string_lossy().into_owned());
+        }
+      }
+      _ => {}
+    }
+    payload
+  }
+
+  fn into_object(self, env: Env) -> Result<Object> {
+    let mut obj = env.create_object()?;
+    obj.set("event", self.event)?;
+    if let Some(name) = self.name {
+      obj.set("name", name)?;
+    }
+    match self.value {
+      Some(PropertyValue::Flag(v)) => obj.set("value", v)?,
+      Some(PropertyValue::Int(v)) => obj.set("value", v)?,
+      Some(PropertyValue::Double(v)) => obj.set("value", v)?,
+      Some(PropertyValue::Str(v)) => obj.set("value", v)?,
+      None => {}
+    }
+    if let Some(prefix) = self.prefix {
+      obj.set("prefix", prefix)?;
+    }
+    if let Some(level) = self.level {
+      obj.set("level", level)?;
+    }
+    if let Some(text) = self.text {
+      obj.set("text", text)?;
+    }
+    Ok(obj)
+  }
+}
+
+/// One `MPV_EVENT_LOG_MESSAGE` delivered to an `enable_logging` callback.
+/// Distinct from `EventPayload` because this channel only ever carries
+/// log messages, so there's no `event`/`name`/`value` to drag along.
+struct LogPayload {
+  prefix: Option<String>,
+  level: Option<String>,
+  text: Option<String>,
+}
+
+impl LogPayload {
+  fn from_raw(msg: &mpv_event_log_message) -> Self {
+    LogPayload {
+      prefix: (!msg.prefix.is_null())
+        .then(|| unsafe { CStr::from_ptr(msg.prefix) }.to_string_lossy().into_owned()),
+      level: (!msg.level.is_null())
+        .then(|| unsafe { CStr::from_ptr(msg.level) }.to_string_lossy().into_owned()),
+      text: (!msg.text.is_null())
+        .then(|| unsafe { CStr::from_ptr(msg.text) }.to_string_lossy().into_owned()),
+    }
+  }
+
+  fn into_object(self, env: Env) -> Result<Object> {
+    let mut obj = env.create_object()?;
+    if let Some(prefix) = self.prefix {
+      obj.set("prefix", prefix)?;
+    }
+    if let Some(level) = self.level {
+      obj.set("level", level)?;
+    }
+    if let Some(text) = self.text {
+      obj.set("text", text)?;
+    }
+    Ok(obj)
+  }
+}
+
+/// Names the kind of native window handle passed to `attach_window`. The
+/// handle goes to mpv's `wid` as `INT64` on every platform; the platform
+/// value itself only appears in `attach_window`'s error message. `init`
+/// picks its `gpu-context` default from the compile target, not from this.
+#[napi]
+#[derive(Debug, PartialEq, Eq)]
+pub enum WindowPlatform {
+  Windows,
+  Linux,
+  MacOs,
+}
+
 #[napi]
 pub struct MpvHandle {
   handle: mpv_handle,
   attached: bool,
+  event_running: Arc<AtomicBool>,
+  event_thread: Option<JoinHandle<()>>,
+  // Boxed so the pointer we hand mpv as `user_data` in register_protocol
+  // stays valid even as this Vec grows (a bare Vec<ProtocolHandlers> would
+  // move/invalidate it on reallocation).
+  #[allow(clippy::vec_box)]
+  protocols: Vec<Box<ProtocolHandlers>>,
+  render_ctx: Option<MpvRenderContextState>,
+  // Shared with the event pump thread (spawned later, by `on_event`) so
+  // `enable_logging` can (re)wire the log sink at any time, including
+  // before that thread exists.
+  log_sink: Arc<Mutex<Option<ThreadsafeFunction<LogPayload, ErrorStrategy::Fatal>>>>,
+  // Set by `set_log_level`/`enable_logging` so `on_event` knows not to
+  // clobber an explicitly-requested level back down to its own default.
+  log_level_requested: AtomicBool,
 }
 
 #[napi]
@@ -202,24 +1009,29 @@ impl MpvHandle {
       if let Some(set_log_level) = funcs.set_log_level {
         let _ = set_log_level(h, 0);
       }
-      Ok(MpvHandle { handle: h, attached: false })
+      Ok(MpvHandle {
+        handle: h,
+        attached: false,
+        event_running: Arc::new(AtomicBool::new(false)),
+        event_thread: None,
+        protocols: Vec::new(),
+        render_ctx: None,
+        log_sink: Arc::new(Mutex::new(None)),
+        log_level_requested: AtomicBool::new(false),
+      })
     }
   }
 
   #[napi]
   pub fn init(&self, _options: Option<Object>) -> Result<()> {
     let funcs = get_mpv_funcs()?;
+    // wid should already be set via attach_window before calling init.
     unsafe {
-      // Video output options (set before init)
-      // Note: wid should already be set via attach_hwnd before calling init
-      // Video output options - try gpu first, fallback to direct3d if needed
-      // wid should already be set via attach_hwnd
       let opts = [
         ("force-window", "yes"),
         ("keep-open", "yes"),
         ("ytdl", "no"),
         ("vo", "gpu"), // Use gpu VO with wid for hardware acceleration
-        ("gpu-context", "d3d11"), // Direct3D 11 context for Windows
         ("hwdec", "auto-safe"), // Hardware decoding
         ("video-sync", "display-resample"), // Sync to display
       ];
@@ -228,6 +1040,19 @@ impl MpvHandle {
         let cv = CString::new(v).unwrap();
         check_err((funcs.set_option_string)(self.handle, ck.as_ptr(), cv.as_ptr()), k)?;
       }
+      // gpu-context picks the platform-native GPU API. On Windows mpv's
+      // own auto-detection doesn't reliably land on d3d11, so force it;
+      // on Linux/macOS leave it unset so mpv picks its own default
+      // (EGL/GLX on Linux, the cocoa-cb path on macOS).
+      #[cfg(target_os = "windows")]
+      {
+        let key = CString::new("gpu-context").unwrap();
+        let value = CString::new("d3d11").unwrap();
+        check_err(
+          (funcs.set_option_string)(self.handle, key.as_ptr(), value.as_ptr()),
+          "gpu-context",
+        )?;
+      }
       check_err((funcs.initialize)(self.handle), "initialize")
     }
   }
@@ -243,51 +1068,52 @@ impl MpvHandle {
     }
   }
 
-  /// Attach to a native HWND (Windows). This sets the "wid" option.
+  /// Attach to a native window handle, interpreted per `platform`:
+  /// - Windows: the full `HWND`, passed through as `INT64` without truncation.
+  /// - Linux: an X11 `Window` XID.
+  /// - macOS: an `NSView*` pointer (cocoa-cb embedding).
+  /// This sets the "wid" option/property, trying the option form first
+  /// (works before init) and falling back to the property form (works
+  /// after init).
+  ///
+  /// Requires the napi crate's `napi6` feature (for `BigInt` support).
   #[napi]
-  pub fn attach_hwnd(&mut self, hwnd: u32) -> Result<()> {
+  pub fn attach_window(&mut self, handle: BigInt, platform: WindowPlatform) -> Result<()> {
     let funcs = get_mpv_funcs()?;
+    let (value, _lossless) = handle.get_i64();
     unsafe {
       let key = CString::new("wid").unwrap();
-      let hwnd64: i64 = hwnd as i64;
-      
-      // Try setting as option first (works before init) - CHECK THE RESULT
+
       let opt_result = (funcs.set_option)(
         self.handle,
         key.as_ptr(),
         mpv_format::MPV_FORMAT_INT64,
-        &hwnd64 as *const i64 as *const c_void,
+        &value as *const i64 as *const c_void,
       );
-      
+
       if opt_result < 0 {
-        // If setting as option failed, try as property (works after init)
         let prop_key = CString::new("wid").unwrap();
         let prop_result = (funcs.set_property)(
           self.handle,
           prop_key.as_ptr(),
           mpv_format::MPV_FORMAT_INT64,
-          &hwnd64 as *const i64 as *const c_void,
+          &value as *const i64 as *const c_void,
         );
         if prop_result < 0 {
           return Err(Error::from_reason(format!(
-            "Failed to set wid: option={}, property={}, hwnd={}",
-            opt_result, prop_result, hwnd
+            "Failed to set wid: option={opt_result}, property={prop_result}, handle={value}, platform={platform:?}"
           )));
         }
-        // Property set succeeded
       } else {
-        // Option was set successfully, also set as property for redundancy
         let prop_key = CString::new("wid").unwrap();
-        let prop_result = (funcs.set_property)(
+        // Option was already set successfully; also set as property for
+        // redundancy, but don't fail the call if that second write fails.
+        let _ = (funcs.set_property)(
           self.handle,
           prop_key.as_ptr(),
           mpv_format::MPV_FORMAT_INT64,
-          &hwnd64 as *const i64 as *const c_void,
+          &value as *const i64 as *const c_void,
         );
-        // Don't fail if property set fails - option was already set
-        if prop_result < 0 {
-          // Log but don't fail
-        }
       }
       self.attached = true;
       Ok(())
@@ -382,11 +1208,17 @@ impl MpvHandle {
   }
 
   #[napi]
-  pub fn shutdown(&self) -> Result<()> {
+  pub fn shutdown(&mut self) -> Result<()> {
+    if self.handle.is_null() {
+      return Ok(()); // already shut down; avoid double-destroy
+    }
+    self.stop_event_pump();
+    self.free_render_context();
     let funcs = get_mpv_funcs()?;
     unsafe {
       (funcs.destroy)(self.handle);
     }
+    self.handle = ptr::null_mut();
     Ok(())
   }
 
@@ -445,10 +1277,682 @@ impl MpvHandle {
     }
     Ok(obj)
   }
+
+  /// Subscribe to mpv's event stream. `callback` is invoked with a small
+  /// object per event: `{ event, name?, value?, prefix?, level?, text? }`.
+  /// Runs a dedicated thread looping on `mpv_wait_event`; stops itself on
+  /// `MPV_EVENT_SHUTDOWN` and is joined in `shutdown`/`Drop` so the thread
+  /// never touches the handle after `mpv_destroy`.
+  #[napi]
+  pub fn on_event(&mut self, callback: JsFunction) -> Result<()> {
+    let funcs = get_mpv_funcs()?;
+    if self.event_thread.is_some() {
+      return Err(Error::from_reason("event pump already running"));
+    }
+    // Start receiving log-message events too, unless the caller already
+    // picked a level via set_log_level/enable_logging - don't clobber it.
+    if !self.log_level_requested.load(Ordering::SeqCst) {
+      let min_level = CString::new("info").unwrap();
+      let _ = unsafe { (funcs.request_log_messages)(self.handle, min_level.as_ptr()) };
+    }
+
+    let tsfn: ThreadsafeFunction<EventPayload, ErrorStrategy::Fatal> =
+      callback.create_threadsafe_function(0, |ctx: ThreadSafeCallContext<EventPayload>| {
+        ctx.value.into_object(ctx.env).map(|obj| vec![obj])
+      })?;
+
+    let running = self.event_running.clone();
+    running.store(true, Ordering::SeqCst);
+    let handle = SendHandle(self.handle);
+    let log_sink = self.log_sink.clone();
+
+    let join = std::thread::spawn(move || {
+      let handle = handle; // moved in for its Send impl
+      while running.load(Ordering::SeqCst) {
+        let ev = unsafe { (funcs.wait_event)(handle.0, -1.0) };
+        if ev.is_null() {
+          continue;
+        }
+        // Copy everything out of the event now: it's only valid until the
+        // next mpv_wait_event call on this same thread.
+        let event = unsafe { &*ev };
+        let event_id = event.event_id;
+        if event_id == mpv_event_id::MPV_EVENT_NONE {
+          continue;
+        }
+        if event_id == mpv_event_id::MPV_EVENT_LOG_MESSAGE && !event.data.is_null() {
+          if let Ok(sink) = log_sink.lock() {
+            if let Some(sink) = sink.as_ref() {
+              let msg = unsafe { &*(event.data as *const mpv_event_log_message) };
+              sink.call(LogPayload::from_raw(msg), ThreadsafeFunctionCallMode::NonBlocking);
+            }
+          }
+        }
+        let payload = EventPayload::from_raw(event);
+        tsfn.call(payload, ThreadsafeFunctionCallMode::NonBlocking);
+        if event_id == mpv_event_id::MPV_EVENT_SHUTDOWN {
+          running.store(false, Ordering::SeqCst);
+          break;
+        }
+      }
+    });
+    self.event_thread = Some(join);
+    Ok(())
+  }
+
+  /// Raises or lowers the verbosity of `MPV_EVENT_LOG_MESSAGE`/
+  /// `enable_logging` output. `level` is one of mpv's own log level names:
+  /// `"no"`, `"fatal"`, `"error"`, `"warn"`, `"info"`, `"v"`, `"debug"`,
+  /// `"trace"`. Can be called at any time, before or after `on_event`/
+  /// `enable_logging`.
+  #[napi]
+  pub fn set_log_level(&self, level: String) -> Result<()> {
+    let funcs = get_mpv_funcs()?;
+    let result = unsafe {
+      let c_level = CString::new(level).map_err(|e| Error::from_reason(e.to_string()))?;
+      check_err((funcs.request_log_messages)(self.handle, c_level.as_ptr()), "request_log_messages")
+    };
+    self.log_level_requested.store(true, Ordering::SeqCst);
+    result
+  }
+
+  /// Subscribe to mpv's own log messages (decoder/hwdec fallback
+  /// diagnostics, etc), separately from `on_event`'s generic stream.
+  /// `callback` is invoked with `{ prefix?, level?, text? }` per message.
+  /// Requests `"info"`-level messages if `set_log_level` hasn't already
+  /// been called; `create` otherwise silences logging entirely. Delivery
+  /// only actually happens once `on_event` has started the pump thread
+  /// that reads `mpv_wait_event`.
+  #[napi]
+  pub fn enable_logging(&mut self, callback: JsFunction) -> Result<()> {
+    if !self.log_level_requested.swap(true, Ordering::SeqCst) {
+      let funcs = get_mpv_funcs()?;
+      unsafe {
+        let min_level = CString::new("info").unwrap();
+        let _ = (funcs.request_log_messages)(self.handle, min_level.as_ptr());
+      }
+    }
+    let tsfn: ThreadsafeFunction<LogPayload, ErrorStrategy::Fatal> =
+      callback.create_threadsafe_function(0, |ctx: ThreadSafeCallContext<LogPayload>| {
+        ctx.value.into_object(ctx.env).map(|obj| vec![obj])
+      })?;
+    *self.log_sink.lock().map_err(|e| Error::from_reason(e.to_string()))? = Some(tsfn);
+    Ok(())
+  }
+
+  /// Register for push notifications on a property (e.g. `time-pos`,
+  /// `pause`, `duration`) instead of polling `get_state`. Requires
+  /// `on_event` to have been called first to receive the resulting
+  /// `property-change` events. `format` is one of `"flag"`, `"int64"`,
+  /// `"double"`, `"string"`.
+  #[napi]
+  pub fn observe_property(&self, name: String, format: String) -> Result<()> {
+    let funcs = get_mpv_funcs()?;
+    let fmt = match format.as_str() {
+      "flag" => mpv_format::MPV_FORMAT_FLAG,
+      "int64" => mpv_format::MPV_FORMAT_INT64,
+      "double" => mpv_format::MPV_FORMAT_DOUBLE,
+      "string" => mpv_format::MPV_FORMAT_STRING,
+      other => return Err(Error::from_reason(format!("unsupported observe format: {other}"))),
+    };
+    unsafe {
+      let c_name = CString::new(name).map_err(|e| Error::from_reason(e.to_string()))?;
+      check_err((funcs.observe_property)(self.handle, 0, c_name.as_ptr(), fmt), "observe_property")
+    }
+  }
+
+  /// Registers a custom stream protocol (e.g. `myscheme://...`) backed by
+  /// JS callbacks, so `load()` can stream from sources mpv can't open
+  /// itself (torrent pieces, in-memory buffers, etc). `handlers` must have
+  /// `open(uri) -> streamId`, `read(streamId, size) -> Buffer`,
+  /// `seek(streamId, offset) -> i64`, `size(streamId) -> i64`, and
+  /// `close(streamId)`. All five are called from mpv's demuxer thread via a
+  /// threadsafe function, which blocks until the JS-side return value arrives.
+  #[napi]
+  pub fn register_protocol(&mut self, env: Env, scheme: String, handlers: Object) -> Result<()> {
+    let funcs = get_mpv_funcs()?;
+    let stream_cb_add_ro = funcs
+      .stream_cb_add_ro
+      .ok_or_else(|| Error::from_reason("mpv_stream_cb_add_ro not available in this libmpv build"))?;
+
+    let open_fn: JsFunction = handlers.get_named_property("open")?;
+    let read_fn: JsFunction = handlers.get_named_property("read")?;
+    let seek_fn: JsFunction = handlers.get_named_property("seek")?;
+    let size_fn: JsFunction = handlers.get_named_property("size")?;
+    let close_fn: JsFunction = handlers.get_named_property("close")?;
+
+    // Shared with each handler's tsfn closure below so the closure can
+    // resolve the callable JsFunction on every call; also kept in
+    // `ProtocolHandlers.refs` so Drop can unref all five (napi's Ref
+    // otherwise asserts it was unreffed before being dropped).
+    let open_ref = Arc::new(Mutex::new(Some(env.create_reference(open_fn)?)));
+    let read_ref = Arc::new(Mutex::new(Some(env.create_reference(read_fn)?)));
+    let seek_ref = Arc::new(Mutex::new(Some(env.create_reference(seek_fn)?)));
+    let size_ref = Arc::new(Mutex::new(Some(env.create_reference(size_fn)?)));
+    let close_ref = Arc::new(Mutex::new(Some(env.create_reference(close_fn)?)));
+    let refs = vec![open_ref.clone(), read_ref.clone(), seek_ref.clone(), size_ref.clone(), close_ref.clone()];
+
+    // Vehicle function: create_threadsafe_function auto-invokes the function
+    // it was built from with whatever the callback below returns, so we keep
+    // that a no-op and do the real call to the registered handler ourselves.
+    let noop = env.create_function("mpvProtocolNoop", js_noop_callback)?;
+
+    let open_tsfn = noop.create_threadsafe_function(
+      0,
+      move |ctx: ThreadSafeCallContext<OpenCall>| {
+        let (uri, reply) = ctx.value;
+        let guard = open_ref.lock().map_err(|e| Error::from_reason(e.to_string()))?;
+        let func: JsFunction = ctx.env.get_reference_value(
+          guard.as_ref().ok_or_else(|| Error::from_reason("protocol handler released"))?,
+        )?;
+        let arg = ctx.env.create_string(&uri)?;
+        let result = func
+          .call(None, &[arg])
+          .and_then(|v| v.coerce_to_number()?.get_int64());
+        let _ = reply.send(result);
+        Ok(Vec::<JsUnknown>::new())
+      },
+    )?;
+
+    let read_tsfn = noop.create_threadsafe_function(
+      0,
+      move |ctx: ThreadSafeCallContext<ReadCall>| {
+        let (stream_id, size, reply) = ctx.value;
+        let guard = read_ref.lock().map_err(|e| Error::from_reason(e.to_string()))?;
+        let func: JsFunction = ctx.env.get_reference_value(
+          guard.as_ref().ok_or_else(|| Error::from_reason("protocol handler released"))?,
+        )?;
+        let args = [ctx.env.create_int64(stream_id)?.into_unknown(), ctx.env.create_int64(size as i64)?.into_unknown()];
+        let result = func.call(None, &args).and_then(|v| {
+          if v.get_type()? != ValueType::Object {
+            return Err(Error::from_reason("read() must return a Buffer"));
+          }
+          let buf: JsBuffer = unsafe { v.cast() };
+          Ok(buf.into_value()?.to_vec())
+        });
+        let _ = reply.send(result);
+        Ok(Vec::<JsUnknown>::new())
+      },
+    )?;
+
+    let seek_tsfn = noop.create_threadsafe_function(
+      0,
+      move |ctx: ThreadSafeCallContext<SeekCall>| {
+        let (stream_id, offset, reply) = ctx.value;
+        let guard = seek_ref.lock().map_err(|e| Error::from_reason(e.to_string()))?;
+        let func: JsFunction = ctx.env.get_reference_value(
+          guard.as_ref().ok_or_else(|| Error::from_reason("protocol handler released"))?,
+        )?;
+        let args = [ctx.env.create_int64(stream_id)?.into_unknown(), ctx.env.create_int64(offset)?.into_unknown()];
+        let result = func
+          .call(None, &args)
+          .and_then(|v| v.coerce_to_number()?.get_int64());
+        let _ = reply.send(result);
+        Ok(Vec::<JsUnknown>::new())
+      },
+    )?;
+
+    let size_tsfn = noop.create_threadsafe_function(
+      0,
+      move |ctx: ThreadSafeCallContext<SizeCall>| {
+        let (stream_id, reply) = ctx.value;
+        let guard = size_ref.lock().map_err(|e| Error::from_reason(e.to_string()))?;
+        let func: JsFunction = ctx.env.get_reference_value(
+          guard.as_ref().ok_or_else(|| Error::from_reason("protocol handler released"))?,
+        )?;
+        let arg = ctx.env.create_int64(stream_id)?;
+        let result = func
+          .call(None, &[arg])
+          .and_then(|v| v.coerce_to_number()?.get_int64());
+        let _ = reply.send(result);
+        Ok(Vec::<JsUnknown>::new())
+      },
+    )?;
+
+    let close_tsfn = noop.create_threadsafe_function(
+      0,
+      move |ctx: ThreadSafeCallContext<CloseCall>| {
+        let (stream_id, reply) = ctx.value;
+        let guard = close_ref.lock().map_err(|e| Error::from_reason(e.to_string()))?;
+        let func: JsFunction = ctx.env.get_reference_value(
+          guard.as_ref().ok_or_else(|| Error::from_reason("protocol handler released"))?,
+        )?;
+        let arg = ctx.env.create_int64(stream_id)?;
+        let result = func.call(None, &[arg]).map(|_| ());
+        let _ = reply.send(result);
+        Ok(Vec::<JsUnknown>::new())
+      },
+    )?;
+
+    let boxed = Box::new(ProtocolHandlers {
+      open: open_tsfn,
+      read: read_tsfn,
+      seek: seek_tsfn,
+      size: size_tsfn,
+      close: close_tsfn,
+      env: env.raw(),
+      refs,
+    });
+    let user_data = boxed.as_ref() as *const ProtocolHandlers as *mut c_void;
+    self.protocols.push(boxed);
+
+    unsafe {
+      let c_scheme = CString::new(scheme).map_err(|e| Error::from_reason(e.to_string()))?;
+      check_err(
+        stream_cb_add_ro(self.handle, c_scheme.as_ptr(), user_data, protocol_open_trampoline),
+        "stream_cb_add_ro",
+      )
+    }
+  }
+
+  /// Runs an arbitrary mpv command, e.g. `["cycle", "pause"]`. Use
+  /// [`MpvHandle::command_node`] instead if the command needs a result.
+  #[napi]
+  pub fn command(&self, args: Vec<String>) -> Result<()> {
+    let funcs = get_mpv_funcs()?;
+    unsafe {
+      let c_args: Vec<CString> = args
+        .into_iter()
+        .map(|a| CString::new(a).map_err(|e| Error::from_reason(e.to_string())))
+        .collect::<Result<_>>()?;
+      let mut ptrs: Vec<*const c_char> = c_args.iter().map(|a| a.as_ptr()).collect();
+      ptrs.push(ptr::null());
+      check_err((funcs.command)(self.handle, ptrs.as_ptr()), "command")
+    }
+  }
+
+  #[napi]
+  pub fn set_property_string(&self, name: String, value: String) -> Result<()> {
+    let funcs = get_mpv_funcs()?;
+    unsafe {
+      let c_name = CString::new(name).map_err(|e| Error::from_reason(e.to_string()))?;
+      let c_value = CString::new(value).map_err(|e| Error::from_reason(e.to_string()))?;
+      check_err(
+        (funcs.set_property_string)(self.handle, c_name.as_ptr(), c_value.as_ptr()),
+        "set_property_string",
+      )
+    }
+  }
+
+  #[napi]
+  pub fn get_property_string(&self, name: String) -> Result<String> {
+    let funcs = get_mpv_funcs()?;
+    unsafe {
+      let c_name = CString::new(name).map_err(|e| Error::from_reason(e.to_string()))?;
+      let raw = (funcs.get_property_string)(self.handle, c_name.as_ptr());
+      if raw.is_null() {
+        return Err(Error::from_reason("property not found"));
+      }
+      let value = CStr::from_ptr(raw).to_string_lossy().into_owned();
+      (funcs.free)(raw as *mut c_void);
+      Ok(value)
+    }
+  }
+
+  /// Sets a property to an arbitrary JSON value, converted to an `mpv_node`
+  /// tree. Use this for properties that aren't plain strings (arrays,
+  /// objects, numbers, booleans) instead of `set_property_string`.
+  #[napi]
+  pub fn set_property(&self, name: String, value: serde_json::Value) -> Result<()> {
+    let funcs = get_mpv_funcs()?;
+    unsafe {
+      let c_name = CString::new(name).map_err(|e| Error::from_reason(e.to_string()))?;
+      let (mut node, _owner) = json_to_mpv_node(&value)?;
+      check_err(
+        (funcs.set_property)(
+          self.handle,
+          c_name.as_ptr(),
+          mpv_format::MPV_FORMAT_NODE,
+          &mut node as *mut mpv_node as *mut c_void,
+        ),
+        "set_property",
+      )
+    }
+  }
+
+  /// Reads a property as a JSON value via `MPV_FORMAT_NODE`, able to
+  /// represent arrays/objects (e.g. `track-list`) that `get_property_string`
+  /// cannot.
+  #[napi]
+  pub fn get_property_node(&self, name: String) -> Result<serde_json::Value> {
+    let funcs = get_mpv_funcs()?;
+    unsafe {
+      let c_name = CString::new(name).map_err(|e| Error::from_reason(e.to_string()))?;
+      let mut out = mem::zeroed::<mpv_node>();
+      check_err(
+        (funcs.get_property)(
+          self.handle,
+          c_name.as_ptr(),
+          mpv_format::MPV_FORMAT_NODE,
+          &mut out as *mut mpv_node as *mut c_void,
+        ),
+        "get_property_node",
+      )?;
+      let json = mpv_node_to_json(&out);
+      (funcs.free_node_contents)(&mut out as *mut mpv_node);
+      json
+    }
+  }
+
+  /// Runs a command that returns data (e.g. `subprocess`), as a JSON value
+  /// in and out via `mpv_command_node`.
+  #[napi]
+  pub fn command_node(&self, args: serde_json::Value) -> Result<serde_json::Value> {
+    let funcs = get_mpv_funcs()?;
+    unsafe {
+      let (mut in_node, _owner) = json_to_mpv_node(&args)?;
+      let mut out_node = mem::zeroed::<mpv_node>();
+      check_err(
+        (funcs.command_node)(self.handle, &mut in_node as *mut mpv_node, &mut out_node as *mut mpv_node),
+        "command_node",
+      )?;
+      let json = mpv_node_to_json(&out_node);
+      (funcs.free_node_contents)(&mut out_node as *mut mpv_node);
+      json
+    }
+  }
+
+  /// Adds and selects an external subtitle track via the `sub-add` command.
+  #[napi]
+  pub fn add_subtitle(&self, url: String, title: Option<String>, lang: Option<String>) -> Result<()> {
+    let mut args = vec!["sub-add".to_string(), url, "select".to_string()];
+    match (title, lang) {
+      (Some(title), Some(lang)) => args.extend([title, lang]),
+      (Some(title), None) => args.push(title),
+      (None, Some(lang)) => args.extend([String::new(), lang]),
+      (None, None) => {}
+    }
+    self.command(args)
+  }
+
+  /// Adds and selects an external audio track via the `audio-add` command.
+  #[napi]
+  pub fn add_audio(&self, url: String, title: Option<String>, lang: Option<String>) -> Result<()> {
+    let mut args = vec!["audio-add".to_string(), url, "select".to_string()];
+    match (title, lang) {
+      (Some(title), Some(lang)) => args.extend([title, lang]),
+      (Some(title), None) => args.push(title),
+      (None, Some(lang)) => args.extend([String::new(), lang]),
+      (None, None) => {}
+    }
+    self.command(args)
+  }
+
+  /// Switches the active subtitle/audio/video track. `value` is a track id,
+  /// `"auto"`, or `"no"`, written to the `sid`/`aid`/`vid` property.
+  #[napi]
+  pub fn set_track(&self, kind: String, value: String) -> Result<()> {
+    let prop = match kind.as_str() {
+      "sub" => "sid",
+      "audio" => "aid",
+      "video" => "vid",
+      other => return Err(Error::from_reason(format!("unknown track kind: {other}"))),
+    };
+    self.set_property_string(prop.to_string(), value)
+  }
+
+  /// Reads the `track-list` property (NODE-format array of maps) and
+  /// returns a JSON array with just the fields callers need.
+  #[napi]
+  pub fn list_tracks(&self) -> Result<serde_json::Value> {
+    let raw = self.get_property_node("track-list".to_string())?;
+    let tracks = raw
+      .as_array()
+      .cloned()
+      .unwrap_or_default()
+      .into_iter()
+      .map(|t| {
+        serde_json::json!({
+          "id": t.get("id").cloned().unwrap_or(serde_json::Value::Null),
+          "type": t.get("type").cloned().unwrap_or(serde_json::Value::Null),
+          "title": t.get("title").cloned().unwrap_or(serde_json::Value::Null),
+          "lang": t.get("lang").cloned().unwrap_or(serde_json::Value::Null),
+          "selected": t.get("selected").cloned().unwrap_or(serde_json::Value::Bool(false)),
+          "codec": t.get("codec").cloned().unwrap_or(serde_json::Value::Null),
+          "default": t.get("default").cloned().unwrap_or(serde_json::Value::Bool(false)),
+        })
+      })
+      .collect();
+    Ok(serde_json::Value::Array(tracks))
+  }
+
+  #[napi]
+  pub fn set_sub_delay(&self, seconds: f64) -> Result<()> {
+    let funcs = get_mpv_funcs()?;
+    unsafe {
+      let key = CString::new("sub-delay").unwrap();
+      check_err(
+        (funcs.set_property)(
+          self.handle,
+          key.as_ptr(),
+          mpv_format::MPV_FORMAT_DOUBLE,
+          &seconds as *const f64 as *const c_void,
+        ),
+        "sub-delay",
+      )
+    }
+  }
+
+  #[napi]
+  pub fn set_sub_scale(&self, scale: f64) -> Result<()> {
+    let funcs = get_mpv_funcs()?;
+    unsafe {
+      let key = CString::new("sub-scale").unwrap();
+      check_err(
+        (funcs.set_property)(
+          self.handle,
+          key.as_ptr(),
+          mpv_format::MPV_FORMAT_DOUBLE,
+          &scale as *const f64 as *const c_void,
+        ),
+        "sub-scale",
+      )
+    }
+  }
+
+  #[napi]
+  pub fn set_sub_visibility(&self, visible: bool) -> Result<()> {
+    let funcs = get_mpv_funcs()?;
+    unsafe {
+      let key = CString::new("sub-visibility").unwrap();
+      let flag: i32 = if visible { 1 } else { 0 };
+      check_err(
+        (funcs.set_property)(
+          self.handle,
+          key.as_ptr(),
+          mpv_format::MPV_FORMAT_FLAG,
+          &flag as *const i32 as *const c_void,
+        ),
+        "sub-visibility",
+      )
+    }
+  }
+
+  /// Creates a render-API context, an alternative to `attach_window`'s
+  /// `wid` embedding that avoids the z-order/resize flicker of handing mpv
+  /// a native window: the host draws mpv's output into its own FBO (or a
+  /// software buffer) instead of mpv owning a window.
+  ///
+  /// `params` fields:
+  /// - `apiType`: `"opengl"` or `"sw"`.
+  /// - `getProcAddress`: `(name: string) => number | bigint`, required for
+  ///   `"opengl"` - resolves GL function pointers on the host's GL context.
+  /// - `onUpdate`: `(frame: number) => void`, called with a monotonic frame counter
+  ///   whenever mpv has produced a new frame; the host should then call
+  ///   `render`/`render_sw` on its own GL/render thread.
+  #[napi]
+  pub fn create_render_context(&mut self, env: Env, params: Object) -> Result<()> {
+    if self.render_ctx.is_some() {
+      return Err(Error::from_reason("render context already created"));
+    }
+    let funcs = get_mpv_funcs()?;
+    let create_fn = funcs
+      .render_context_create
+      .ok_or_else(|| Error::from_reason("mpv_render_context_create not available in this libmpv build"))?;
+    let set_update_fn = funcs
+      .render_context_set_update_callback
+      .ok_or_else(|| Error::from_reason("mpv_render_context_set_update_callback not available in this libmpv build"))?;
+
+    let api_type: String = params.get_named_property("apiType")?;
+    let on_update: JsFunction = params.get_named_property("onUpdate")?;
+    let update_tsfn: ThreadsafeFunction<u32, ErrorStrategy::Fatal> =
+      on_update.create_threadsafe_function(0, |ctx: ThreadSafeCallContext<u32>| Ok(vec![ctx.value]))?;
+    let update_state = Box::new(RenderUpdateState { tsfn: update_tsfn, frame: AtomicU32::new(0) });
+
+    let api_type_c = CString::new(api_type.as_str()).map_err(|e| Error::from_reason(e.to_string()))?;
+    let api_type_param = mpv_render_param {
+      type_: mpv_render_param_type::MPV_RENDER_PARAM_API_TYPE,
+      data: api_type_c.as_ptr() as *mut c_void,
+    };
+
+    let (mut opengl_init, proc_address_state) = if api_type == "opengl" {
+      let get_proc_address: JsFunction = params.get_named_property("getProcAddress")?;
+      let proc_ref = env.create_reference(get_proc_address)?;
+      let state = Box::new(ProcAddressState { env: env.raw(), get_proc_address: proc_ref });
+      let ctx_ptr = state.as_ref() as *const ProcAddressState as *mut c_void;
+      let init = mpv_opengl_init_params {
+        get_proc_address: gl_get_proc_address_trampoline,
+        get_proc_address_ctx: ctx_ptr,
+      };
+      (Some(init), Some(state))
+    } else {
+      (None, None)
+    };
+
+    let mut params_vec = vec![api_type_param];
+    if let Some(init) = opengl_init.as_mut() {
+      params_vec.push(mpv_render_param {
+        type_: mpv_render_param_type::MPV_RENDER_PARAM_OPENGL_INIT_PARAMS,
+        data: init as *mut mpv_opengl_init_params as *mut c_void,
+      });
+    }
+    params_vec.push(mpv_render_param { type_: mpv_render_param_type::MPV_RENDER_PARAM_INVALID, data: ptr::null_mut() });
+
+    let mut ctx: mpv_render_context = ptr::null_mut();
+    let result = unsafe { create_fn(&mut ctx, self.handle, params_vec.as_mut_ptr()) };
+    check_err(result, "mpv_render_context_create")?;
+
+    let update_ptr = update_state.as_ref() as *const RenderUpdateState as *mut c_void;
+    unsafe { set_update_fn(ctx, render_update_trampoline, update_ptr) };
+
+    self.render_ctx = Some(MpvRenderContextState { ctx, update: update_state, proc_address: proc_address_state });
+    Ok(())
+  }
+
+  /// Renders the current frame into the host's GL FBO. Call on the same
+  /// GL context/thread the `getProcAddress` callback resolves functions
+  /// for, after `onUpdate` (from `create_render_context`) fires.
+  #[napi]
+  pub fn render(&self, fbo: i32, width: u32, height: u32, flip: bool) -> Result<()> {
+    let funcs = get_mpv_funcs()?;
+    let render_fn = funcs
+      .render_context_render
+      .ok_or_else(|| Error::from_reason("mpv_render_context_render not available in this libmpv build"))?;
+    let state = self
+      .render_ctx
+      .as_ref()
+      .ok_or_else(|| Error::from_reason("no render context; call create_render_context first"))?;
+
+    let mut fbo_param = mpv_opengl_fbo { fbo, w: width as c_int, h: height as c_int, internal_format: 0 };
+    let mut flip_flag: c_int = if flip { 1 } else { 0 };
+    let mut render_params = [
+      mpv_render_param {
+        type_: mpv_render_param_type::MPV_RENDER_PARAM_OPENGL_FBO,
+        data: &mut fbo_param as *mut mpv_opengl_fbo as *mut c_void,
+      },
+      mpv_render_param {
+        type_: mpv_render_param_type::MPV_RENDER_PARAM_FLIP_Y,
+        data: &mut flip_flag as *mut c_int as *mut c_void,
+      },
+      mpv_render_param { type_: mpv_render_param_type::MPV_RENDER_PARAM_INVALID, data: ptr::null_mut() },
+    ];
+    unsafe { check_err(render_fn(state.ctx, render_params.as_mut_ptr()), "mpv_render_context_render") }
+  }
+
+  /// Portable fallback for when the host has no GL context: renders the
+  /// current frame into a freshly-allocated `Buffer`, 4 bytes per pixel in
+  /// `rgb0` order (red, green, blue, then an unused padding byte - mpv's
+  /// software renderer has no real alpha channel to fill that last byte
+  /// with). Requires `create_render_context` to have been called with
+  /// `apiType: "sw"`.
+  #[napi]
+  pub fn render_sw(&self, width: u32, height: u32) -> Result<Buffer> {
+    let funcs = get_mpv_funcs()?;
+    let render_fn = funcs
+      .render_context_render
+      .ok_or_else(|| Error::from_reason("mpv_render_context_render not available in this libmpv build"))?;
+    let state = self
+      .render_ctx
+      .as_ref()
+      .ok_or_else(|| Error::from_reason("no render context; call create_render_context first"))?;
+
+    let mut buf = vec![0u8; width as usize * height as usize * 4];
+    let mut size: [c_int; 2] = [width as c_int, height as c_int];
+    // mpv's sw renderer only accepts "rgb0"/"bgr0"/"0bgr"/"0rgb" - "rgba"
+    // isn't a valid MPV_RENDER_PARAM_SW_FORMAT and fails every render call.
+    let format = CString::new("rgb0").unwrap();
+    let mut stride: usize = width as usize * 4;
+    let mut render_params = [
+      mpv_render_param { type_: mpv_render_param_type::MPV_RENDER_PARAM_SW_SIZE, data: size.as_mut_ptr() as *mut c_void },
+      mpv_render_param {
+        type_: mpv_render_param_type::MPV_RENDER_PARAM_SW_FORMAT,
+        data: format.as_ptr() as *mut c_void,
+      },
+      mpv_render_param {
+        type_: mpv_render_param_type::MPV_RENDER_PARAM_SW_STRIDE,
+        data: &mut stride as *mut usize as *mut c_void,
+      },
+      mpv_render_param {
+        type_: mpv_render_param_type::MPV_RENDER_PARAM_SW_POINTER,
+        data: buf.as_mut_ptr() as *mut c_void,
+      },
+      mpv_render_param { type_: mpv_render_param_type::MPV_RENDER_PARAM_INVALID, data: ptr::null_mut() },
+    ];
+    unsafe {
+      check_err(render_fn(state.ctx, render_params.as_mut_ptr()), "mpv_render_context_render")?;
+    }
+    Ok(Buffer::from(buf))
+  }
+
+  /// Frees the render context created by `create_render_context`, if any.
+  /// Always called before `mpv_destroy` (from `shutdown`/`Drop`) since mpv
+  /// requires the render context to be torn down first.
+  #[napi]
+  pub fn free_render_context(&mut self) {
+    if let Some(state) = self.render_ctx.take() {
+      if let Ok(funcs) = get_mpv_funcs() {
+        if let Some(free_fn) = funcs.render_context_free {
+          unsafe { free_fn(state.ctx) };
+        }
+      }
+    }
+  }
+
+  /// Unblocks and joins the event pump thread. `mpv_wait_event(-1.0)` only
+  /// returns when an event arrives, so we ask mpv to quit (which always
+  /// emits `MPV_EVENT_SHUTDOWN`) rather than relying on the flag alone.
+  fn stop_event_pump(&mut self) {
+    if self.event_thread.is_none() {
+      return;
+    }
+    self.event_running.store(false, Ordering::SeqCst);
+    if let Ok(funcs) = get_mpv_funcs() {
+      unsafe {
+        let cmd = CString::new("quit").unwrap();
+        let args: [*const c_char; 2] = [cmd.as_ptr(), ptr::null()];
+        let _ = (funcs.command)(self.handle, args.as_ptr());
+      }
+    }
+    if let Some(join) = self.event_thread.take() {
+      let _ = join.join();
+    }
+  }
 }
 
 impl Drop for MpvHandle {
   fn drop(&mut self) {
+    self.stop_event_pump();
+    self.free_render_context();
     if let Ok(funcs) = get_mpv_funcs() {
       unsafe {
         if !self.handle.is_null() {